@@ -1,25 +1,179 @@
 use anyhow::Result;
 use plonky2::field::types::Field;
+use plonky2::iop::target::Target;
 use plonky2::iop::witness::{PartialWitness, WitnessWrite};
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 use plonky2::plonk::circuit_data::CircuitConfig;
 use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use rayon::prelude::*;
 use serde_json::Value;
 use std::fs;
 use std::time::Instant;
+use veritas::hashing;
+use veritas::ivc;
+use veritas::lookup;
+use veritas::steps::StepCircuit;
 
-static H : usize = 720;
-static W : usize = 1280;
-static BLUR_H : usize = 6;
-static BLUR_W : usize = 6;
+static H: usize = 720;
+static W: usize = 1280;
+static BLUR_H: usize = 6;
+static BLUR_W: usize = 6;
+
+/// Builds and proves-ready-witnesses a single horizontal row tile as a
+/// [`StepCircuit`]. `row_start`/`row_end` is the tile's row range in the
+/// full image (`[row_start, row_end)`), `cv_in`/`block_offset_in` is the
+/// running BLAKE3-style chaining value/block counter entering this tile
+/// (`hashing::IV`-derived for the first tile, chained from the previous
+/// tile otherwise), and `is_last_tile` controls whether this tile's output
+/// absorption sets the ROOT flag on the final block.
+///
+/// Both the original rows and this tile's output rows are absorbed into one
+/// running digest (original bytes, then output bytes, per tile) so that
+/// `ivc::fold_steps`'s existing `out_digest == next.in_digest` chaining
+/// constraint is exactly what's needed to stitch tiles into one commitment
+/// — no new recursion logic required.
+///
+/// The blur only touches rows `1..=BLUR_H`, which are required to lie
+/// entirely inside the first tile (checked by the caller); other tiles
+/// simply pass every pixel through unchanged.
+#[allow(clippy::too_many_arguments)]
+fn build_blur_tile<F, C, const D: usize>(
+    row_start: usize,
+    row_end: usize,
+    w_r_vals: &[Vec<usize>],
+    x_r_vals: &[Vec<usize>],
+    cv_in: [u32; 8],
+    block_offset_in: u64,
+    is_last_tile: bool,
+) -> StepCircuit<F, C, D>
+where
+    F: plonky2::hash::hash_types::RichField + plonky2::field::extension::Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+    let mut pw = PartialWitness::new();
+
+    let byte_table = lookup::add_byte_range_table(&mut builder);
+    let tile_rows = row_end - row_start;
+
+    let mut orig_targets = Vec::with_capacity(tile_rows);
+    for _ in 0..tile_rows {
+        let mut row_targets = Vec::with_capacity(W);
+        for _ in 0..W {
+            let t = builder.add_virtual_target();
+            lookup::constrain_byte(&mut builder, byte_table, t);
+            row_targets.push(t);
+        }
+        orig_targets.push(row_targets);
+    }
+
+    let mut out_targets: Vec<Vec<Target>> = Vec::with_capacity(tile_rows);
+    let mut blur_targets = Vec::new(); // freshly-computed blurred pixels, set in the witness below
+    for local_i in 0..tile_rows {
+        let i = row_start + local_i;
+        let mut out_row = Vec::with_capacity(W);
+        for j in 0..W {
+            if i > 0 && i < 1 + BLUR_H && j > 0 && j < 1 + BLUR_W {
+                let all_r = vec![
+                    orig_targets[local_i - 1][j - 1],
+                    orig_targets[local_i - 1][j],
+                    orig_targets[local_i - 1][j + 1],
+                    orig_targets[local_i][j - 1],
+                    orig_targets[local_i][j],
+                    orig_targets[local_i][j + 1],
+                    orig_targets[local_i + 1][j - 1],
+                    orig_targets[local_i + 1][j],
+                    orig_targets[local_i + 1][j + 1],
+                ];
+                let s_r = builder.add_many(all_r);
+                let s_r_shift = builder.add_const(s_r, F::from_canonical_u32(4));
+
+                let x_r = builder.add_virtual_target();
+                let x_r_times_9 = builder.mul_const(F::from_canonical_u32(9), x_r);
+                let rem_r = builder.sub(s_r_shift, x_r_times_9);
+                builder.range_check(rem_r, 4);
+                let rem_r_plus_7 = builder.add_const(rem_r, F::from_canonical_u32(7));
+                builder.range_check(rem_r_plus_7, 4);
+
+                blur_targets.push(x_r);
+                out_row.push(x_r);
+            } else {
+                out_row.push(orig_targets[local_i][j]);
+            }
+        }
+        out_targets.push(out_row);
+    }
+
+    let orig_flat: Vec<Target> = orig_targets.iter().flatten().copied().collect();
+    let out_flat: Vec<Target> = out_targets.iter().flatten().copied().collect();
+
+    let cv_in_t: hashing::Digest = cv_in.map(|w| builder.constant(F::from_canonical_u32(w)));
+    let (cv_mid_t, block_offset_mid) = hashing::commit_blocks(&mut builder, cv_in_t, &orig_flat, block_offset_in, false);
+    let (cv_out_t, _) = hashing::commit_blocks(&mut builder, cv_mid_t, &out_flat, block_offset_mid, is_last_tile);
+
+    for word in cv_in_t {
+        builder.register_public_input(word);
+    }
+    for word in cv_out_t {
+        builder.register_public_input(word);
+    }
+
+    for local_i in 0..tile_rows {
+        for j in 0..W {
+            pw.set_target(orig_targets[local_i][j], F::from_canonical_u32(w_r_vals[row_start + local_i][j] as u32));
+        }
+    }
+    let mut blur_idx = 0;
+    for i in 1..(1 + BLUR_H) {
+        if i < row_start || i >= row_end {
+            continue;
+        }
+        for j in 1..(1 + BLUR_W) {
+            pw.set_target(blur_targets[blur_idx], F::from_canonical_u32(x_r_vals[i][j] as u32));
+            blur_idx += 1;
+        }
+    }
+
+    let orig_bytes: Vec<u32> = w_r_vals[row_start..row_end].iter().flatten().map(|&v| v as u32).collect();
+    let mut out_bytes = Vec::with_capacity(tile_rows * W);
+    for local_i in 0..tile_rows {
+        let i = row_start + local_i;
+        for j in 0..W {
+            if i > 0 && i < 1 + BLUR_H && j > 0 && j < 1 + BLUR_W {
+                out_bytes.push(x_r_vals[i][j] as u32);
+            } else {
+                out_bytes.push(w_r_vals[i][j] as u32);
+            }
+        }
+    }
+    let (cv_mid, block_offset_mid_plain) = hashing::commit_blocks_plain(cv_in, &orig_bytes, block_offset_in, false);
+    let (cv_out, _) = hashing::commit_blocks_plain(cv_mid, &out_bytes, block_offset_mid_plain, is_last_tile);
+    debug_assert_eq!(block_offset_mid, block_offset_mid_plain);
+
+    StepCircuit {
+        data: builder.build::<C>(),
+        witness: pw,
+        in_digest: cv_in,
+        out_digest: cv_out,
+    }
+}
 
 fn main() -> Result<()> {
     const D: usize = 2;
     type C = PoseidonGoldilocksConfig;
     type F = <C as GenericConfig<D>>::F;
 
-    // Load image data from JSON
-    let json_path = std::env::args().nth(1).expect("Usage: blur-benchmark <json_file_path>");
+    let mut args = std::env::args().skip(1);
+    let json_path = args.next().expect("Usage: blur-benchmark <json_file_path> [tile_rows] [threads]");
+    let tile_rows: usize = args.next().map(|s| s.parse().expect("tile_rows must be a number")).unwrap_or(128);
+    let threads: usize = args
+        .next()
+        .map(|s| s.parse().expect("threads must be a number"))
+        .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1));
+
+    assert!(tile_rows >= BLUR_H + 2, "tile_rows must be large enough to hold the whole blur region in one tile");
+
     let json_str = fs::read_to_string(&json_path)?;
     let data: Value = serde_json::from_str(&json_str)?;
 
@@ -27,150 +181,102 @@ fn main() -> Result<()> {
     let blurred = data["blurred"].as_array().unwrap();
 
     let mut w_r_vals = Vec::new();
-    let mut x_r_vals = Vec::new();
-
-    // Load original image
     for row in original {
-        let row_array = row.as_array().unwrap();
-        let mut pixel_row = Vec::new();
-        for pixel in row_array {
-            pixel_row.push(pixel.as_u64().unwrap() as usize);
-        }
-        w_r_vals.push(pixel_row);
+        w_r_vals.push(row.as_array().unwrap().iter().map(|p| p.as_u64().unwrap() as usize).collect::<Vec<_>>());
     }
-
-    // Load blurred image
+    let mut x_r_vals = Vec::new();
     for row in blurred {
-        let row_array = row.as_array().unwrap();
-        let mut pixel_row = Vec::new();
-        for pixel in row_array {
-            pixel_row.push(pixel.as_u64().unwrap() as usize);
-        }
-        x_r_vals.push(pixel_row);
+        x_r_vals.push(row.as_array().unwrap().iter().map(|p| p.as_u64().unwrap() as usize).collect::<Vec<_>>());
     }
 
-    // Verify dimensions match
     if w_r_vals.len() != H || w_r_vals[0].len() != W {
-        panic!("Image dimensions mismatch: expected {}x{}, got {}x{}", 
-               H, W, w_r_vals.len(), w_r_vals[0].len());
+        panic!("Image dimensions mismatch: expected {}x{}, got {}x{}", H, W, w_r_vals.len(), w_r_vals[0].len());
     }
     if x_r_vals.len() != H || x_r_vals[0].len() != W {
-        panic!("Blurred image dimensions mismatch: expected {}x{}, got {}x{}", 
-               H, W, x_r_vals.len(), x_r_vals[0].len());
+        panic!("Blurred image dimensions mismatch: expected {}x{}, got {}x{}", H, W, x_r_vals.len(), x_r_vals[0].len());
     }
 
-    // Circuit build time (equivalent to VIMz "Key Generation")
-    let circuit_start = Instant::now();
-    let config = CircuitConfig::standard_recursion_config();
-    let mut builder = CircuitBuilder::<F, D>::new(config);
-
-    let mut w_r_targets = Vec::new();
-    for _ in 0..H {
-        let mut w_r_target_row = Vec::new();
-        for _ in 0..W {
-            let w_r = builder.add_virtual_target();
-            w_r_target_row.push(w_r);
-        }  
-        w_r_targets.push(w_r_target_row);       
+    // Precompute each tile's row range and chained-in digest natively
+    // (cheap) so tile circuits can be built fully independently afterward.
+    let mut tile_ranges = Vec::new();
+    let mut row = 0;
+    while row < H {
+        let end = (row + tile_rows).min(H);
+        tile_ranges.push((row, end));
+        row = end;
     }
+    let num_tiles = tile_ranges.len();
 
-    let mut x_r_targets = Vec::new();
-    for i in 0..H {
-        let mut x_r_target_row = Vec::new();
-        for j in 0..W {
-            if i > 0 && i < 1 + BLUR_H && j > 0 && j < 1 + BLUR_W {
-                // in blur region
-                let mut all_r = Vec::new();
-
-                all_r.push(w_r_targets[i-1][j-1]);
-                all_r.push(w_r_targets[i-1][j]);
-                all_r.push(w_r_targets[i-1][j+1]);
-                all_r.push(w_r_targets[i][j-1]);
-                all_r.push(w_r_targets[i][j]);
-                all_r.push(w_r_targets[i][j+1]);
-                all_r.push(w_r_targets[i+1][j-1]);
-                all_r.push(w_r_targets[i+1][j]);
-                all_r.push(w_r_targets[i+1][j+1]);
-                
-
-                let s_r = builder.add_many(all_r);
-
-                // add 4 this so that remainder moves from value in [-4,4] to value in [0,8]
-                let s_r_shift = builder.add_const(s_r, F::from_canonical_u32(4));
-                
-                let x_r = builder.add_virtual_target();
-                x_r_target_row.push(x_r);
-                let x_r_times_9 = builder.mul_const(F::from_canonical_u32(9), x_r);
-
-                let rem_r = builder.sub(s_r_shift, x_r_times_9);
-
-                // To check that rem \in [0, 8], we must check that rem < 2^4 and that
-                // rem + 7 < 2^4
-                builder.range_check(rem_r, 4);
-                let rem_r_plus_7 = builder.add_const(rem_r, F::from_canonical_u32(7));
-                builder.range_check(rem_r_plus_7, 4);
-
+    let mut cv = hashing::IV;
+    let mut block_offset = 0u64;
+    let mut tile_cv_in = Vec::with_capacity(num_tiles);
+    for (t, &(start, end)) in tile_ranges.iter().enumerate() {
+        tile_cv_in.push((cv, block_offset));
+        let is_last_tile = t + 1 == num_tiles;
+        let orig_bytes: Vec<u32> = w_r_vals[start..end].iter().flatten().map(|&v| v as u32).collect();
+        let mut out_bytes = Vec::with_capacity((end - start) * W);
+        for i in start..end {
+            for j in 0..W {
+                if i > 0 && i < 1 + BLUR_H && j > 0 && j < 1 + BLUR_W {
+                    out_bytes.push(x_r_vals[i][j] as u32);
+                } else {
+                    out_bytes.push(w_r_vals[i][j] as u32);
+                }
             }
-            else {
-                builder.register_public_input(w_r_targets[i][j]);
-            } 
-        }
-        if x_r_target_row.len() > 0 {
-             x_r_targets.push(x_r_target_row);
         }
+        let (cv_mid, block_offset_mid) = hashing::commit_blocks_plain(cv, &orig_bytes, block_offset, false);
+        let (cv_next, block_offset_next) = hashing::commit_blocks_plain(cv_mid, &out_bytes, block_offset_mid, is_last_tile);
+        cv = cv_next;
+        block_offset = block_offset_next;
     }
-    
-    let data = builder.build::<C>();
+    let final_combined_digest = cv;
+
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build()?;
+
+    // Circuit build + witness-assignment time (equivalent to VIMz "Key
+    // Generation"), parallelized across tiles instead of one big
+    // single-threaded double loop over all 720*1280 pixels.
+    let circuit_start = Instant::now();
+    let tiles: Vec<StepCircuit<F, C, D>> = pool.install(|| {
+        tile_ranges
+            .par_iter()
+            .enumerate()
+            .map(|(t, &(start, end))| {
+                let (cv_in, block_offset_in) = tile_cv_in[t];
+                let is_last_tile = t + 1 == num_tiles;
+                build_blur_tile::<F, C, D>(start, end, &w_r_vals, &x_r_vals, cv_in, block_offset_in, is_last_tile)
+            })
+            .collect()
+    });
     let circuit_time = circuit_start.elapsed();
 
-    // Get circuit statistics
-    let num_gates = data.common.gates.len();
-    // Calculate number of variables: H*W input pixels + (BLUR_H*BLUR_W) blurred output pixels
+    let num_gates: usize = tiles.iter().map(|t| t.data.common.gates.len()).sum();
     let num_variables = H * W + BLUR_H * BLUR_W;
 
-    // Output metrics in VIMz-compatible format
+    println!("Tiles: {} ({} rows each, {} threads)", num_tiles, tile_rows, threads);
     println!("Circuit build took: {:.9}s", circuit_time.as_secs_f64());
     println!("Number of constraints: {}", num_gates);
     println!("Number of variables: {}", num_variables);
 
-    // Proof generation time (equivalent to VIMz "RecursiveSNARK creation")
+    // Proof generation time: each tile proves concurrently, then the tile
+    // proofs are recursively merged into one (the merge itself is
+    // necessarily sequential — each fold step verifies the previous one).
     let proof_start = Instant::now();
-    let mut pw = PartialWitness::new();
-
-    for i in 0..H {
-        for j in 0..W {
-            pw.set_target(w_r_targets[i][j], F::from_canonical_u32(w_r_vals[i][j] as u32));
-       }
-    }
-
-    for i in 0..BLUR_H {
-        for j in 0..BLUR_W {
-            pw.set_target(x_r_targets[i][j], F::from_canonical_u32(x_r_vals[i+1][j+1] as u32));
-        }
-    }
-
-
-    let proof = data.prove(pw)?;
+    let folded = ivc::fold_steps_parallel(tiles, threads)?;
     let proof_time = proof_start.elapsed();
     println!("Proof generation took: {:.9}s", proof_time.as_secs_f64());
 
-    let mut ctr = 0;
-    for i in 0..H {
-        for j in 0..W {
-            if !(i > 0 && i < 1 + BLUR_H && j > 0 && j < 1 + BLUR_W) {
-                // Public inputs are the original border pixels (w_r_vals), not blurred ones
-                assert!(w_r_vals[i][j] as u64 == proof.public_inputs[ctr].0,
-                    "Public input mismatch at ({}, {}): expected {}, got {}", 
-                    i, j, w_r_vals[i][j], proof.public_inputs[ctr].0);
-                ctr += 1;
-            }
-
-        }
-    }
-
-    // Verification time (equivalent to VIMz "RecursiveSNARK verify")
     let verify_start = Instant::now();
-    let res = data.verify(proof);
+    for (i, expected) in final_combined_digest.into_iter().enumerate() {
+        assert!(
+            folded.proof.public_inputs[8 + i].0 == expected as u64,
+            "Combined digest word mismatch at index {}: expected {}, got {}",
+            i,
+            expected,
+            folded.proof.public_inputs[8 + i].0
+        );
+    }
+    let res = folded.data.verify(folded.proof);
     let _ = res?;
     let verify_time = verify_start.elapsed();
     println!("Verification took: {:.9}ms", verify_time.as_secs_f64() * 1000.0);
@@ -6,7 +6,11 @@ use plonky2::plonk::circuit_data::CircuitConfig;
 use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
 use serde_json::Value;
 use std::fs;
-use std::time::Instant; 
+use std::path::Path;
+use std::time::Instant;
+use veritas::hashing;
+use veritas::lookup;
+use veritas::serialize;
 
 fn main() -> Result<()> {
     const D: usize = 2;
@@ -47,7 +51,6 @@ fn main() -> Result<()> {
     }
 
     let OLD_SIZE = w_r_vals.len() * w_r_vals[0].len();
-    let NEW_SIZE = x_r_vals.len() * x_r_vals[0].len();
 
     // Flatten to 1D arrays (matching original crop.rs logic)
     let mut w_r_vals_flat = Vec::new();
@@ -91,12 +94,34 @@ fn main() -> Result<()> {
 
     let mut pw = PartialWitness::new();
 
+    // Wire the full original image so the crop can be constrained in-circuit
+    // instead of only being checked against the witness outside the proof.
+    let byte_table = lookup::add_byte_range_table(&mut builder);
+    let mut orig_targets = Vec::new();
+    for _ in 0..OLD_SIZE {
+        let t = builder.add_virtual_target();
+        lookup::constrain_byte(&mut builder, byte_table, t);
+        orig_targets.push(t);
+    }
+
+    // Every cropped-output target IS the corresponding original target
+    // (no fresh virtual target, no separate equality constraint needed) so
+    // the circuit directly enforces that the crop came from this original.
     let mut w_r_targets = Vec::new();
+    for i in 0..crop_height {
+        for j in 0..crop_width {
+            let orig_row = crop_y + i;
+            let orig_col = crop_x + j;
+            w_r_targets.push(orig_targets[orig_row * orig_width + orig_col]);
+        }
+    }
 
-    for _ in 0..NEW_SIZE {
-        let r = builder.add_virtual_target();
-        w_r_targets.push(r);
-        builder.register_public_input(r);    
+    // Commit to the original and cropped images instead of leaking every
+    // pixel as a public input.
+    let original_digest = hashing::commit_image(&mut builder, &orig_targets);
+    let output_digest = hashing::commit_image(&mut builder, &w_r_targets);
+    for word in original_digest.into_iter().chain(output_digest) {
+        builder.register_public_input(word);
     }
 
     let data = builder.build::<C>();
@@ -104,7 +129,7 @@ fn main() -> Result<()> {
 
     // Get circuit statistics
     let num_gates = data.common.gates.len();
-    let num_variables = NEW_SIZE;
+    let num_variables = OLD_SIZE; // cropped targets alias into the original, not fresh variables
 
     // Output metrics in VIMz-compatible format
     println!("Circuit build took: {:.9}s", circuit_time.as_secs_f64());
@@ -114,20 +139,44 @@ fn main() -> Result<()> {
     // Proof generation time (equivalent to VIMz "RecursiveSNARK creation")
     let proof_start = Instant::now();
 
-    for i in 0..NEW_SIZE {
-        pw.set_target(w_r_targets[i], F::from_canonical_u32(x_r_vals_flat[i]));
+    for i in 0..OLD_SIZE {
+        pw.set_target(orig_targets[i], F::from_canonical_u32(w_r_vals_flat[i]));
     }
 
     let proof = data.prove(pw)?;
     let proof_time = proof_start.elapsed();
     println!("Proof generation took: {:.9}s", proof_time.as_secs_f64());
 
+    // Archive the proof plus the verifying key (verifier_only + common) so
+    // it can be checked offline by `examples/verify.rs` without rerunning
+    // the prover. Written next to the input JSON so the proof travels with
+    // the edited image.
+    let proof_dir = Path::new(&json_path).with_extension("proof");
+    serialize::write_proof_bundle(&proof_dir, &data, &proof)?;
+    println!("Proof artifacts written to: {}", proof_dir.display());
+
     // Verification time (equivalent to VIMz "RecursiveSNARK verify")
     let verify_start = Instant::now();
 
-    for i in 0..proof.public_inputs.len() {
-        assert!((proof.public_inputs[i].0) as u32 == x_r_vals_flat[i]);
+    let expected_original_digest = hashing::commit_image_plain(&w_r_vals_flat);
+    let expected_output_digest = hashing::commit_image_plain(&x_r_vals_flat);
+    let expected_digests: Vec<u32> = expected_original_digest
+        .into_iter()
+        .chain(expected_output_digest)
+        .collect();
+    for (i, &expected) in expected_digests.iter().enumerate() {
+        assert!(proof.public_inputs[i].0 == expected as u64,
+            "Digest word mismatch at index {}: expected {}, got {}",
+            i, expected, proof.public_inputs[i].0);
     }
+    fs::write(
+        proof_dir.join("digests.json"),
+        serde_json::json!({
+            "original_digest": expected_digests[0..8],
+            "output_digest": expected_digests[8..16],
+        })
+        .to_string(),
+    )?;
 
     let res = data.verify(proof);
     let _ = res?;
@@ -7,6 +7,8 @@ use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
 use serde_json::Value;
 use std::fs;
 use std::time::Instant;
+use veritas::hashing;
+use veritas::lookup;
 
 fn main() -> Result<()> {
     const D: usize = 2;
@@ -69,9 +71,14 @@ fn main() -> Result<()> {
 
     let mut pw = PartialWitness::new();
 
+    let byte_table = lookup::add_byte_range_table(&mut builder);
+
     let mut r_targets = Vec::new();
     let mut g_targets = Vec::new();
     let mut b_targets = Vec::new();
+    let mut original_bytes = Vec::new();
+    let mut q_targets = Vec::new();
+    let mut r_rem_targets = Vec::new();
 
     for _ in 0..total_pixels {
         let r = builder.add_virtual_target();
@@ -83,6 +90,12 @@ fn main() -> Result<()> {
         let b = builder.add_virtual_target();
         b_targets.push(b);
 
+        lookup::constrain_bytes(&mut builder, byte_table, &[r, g, b]);
+
+        original_bytes.push(r);
+        original_bytes.push(g);
+        original_bytes.push(b);
+
         let mut all = Vec::new();
 
         // VIMz formula: 299*R + 587*G + 114*B
@@ -91,7 +104,37 @@ fn main() -> Result<()> {
         all.push(builder.mul_const(F::from_canonical_u32(114), b));
 
         let s = builder.add_many(all);
-        builder.register_public_input(s);
+
+        // Constrain the actual grayscale byte `q` and remainder `rem` of
+        // `s / 1000`, instead of exposing the raw weighted sum: assert
+        // `s = 1000*q + rem`, range-check `rem` in [0, 999], and clamp `q`
+        // to a valid byte via the lookup table.
+        let q = builder.add_virtual_target();
+        let rem = builder.add_virtual_target();
+        let thousand_q = builder.mul_const(F::from_canonical_u32(1000), q);
+        let reconstructed = builder.add(thousand_q, rem);
+        builder.connect(s, reconstructed);
+        // range_check(rem, 10) alone only bounds rem to [0, 1023], which
+        // admits rem in [1000, 1023] — a prover could then pick q one too
+        // small and rem 1000 too large and still pass. Also range-check
+        // rem + 24 so rem can't exceed 999: if rem were >= 1000, rem + 24
+        // would be >= 1024 and fail the 10-bit check.
+        builder.range_check(rem, 10);
+        let rem_plus_24 = builder.add_const(rem, F::from_canonical_u32(24));
+        builder.range_check(rem_plus_24, 10);
+        lookup::constrain_byte(&mut builder, byte_table, q);
+
+        q_targets.push(q);
+        r_rem_targets.push(rem);
+    }
+
+    // Commit to the original RGB image and the true grayscale output
+    // instead of leaking every pixel (or the raw unconstrained sum) as a
+    // public input.
+    let original_digest = hashing::commit_image(&mut builder, &original_bytes);
+    let output_digest = hashing::commit_image(&mut builder, &q_targets);
+    for word in original_digest.into_iter().chain(output_digest) {
+        builder.register_public_input(word);
     }
 
     let data = builder.build::<C>();
@@ -99,7 +142,7 @@ fn main() -> Result<()> {
 
     // Get circuit statistics
     let num_gates = data.common.gates.len();
-    let num_variables = total_pixels * 3; // R, G, B for each pixel
+    let num_variables = total_pixels * 5; // R, G, B, quotient, remainder for each pixel
 
     // Output metrics in VIMz-compatible format
     println!("Circuit build took: {:.9}s", circuit_time.as_secs_f64());
@@ -113,6 +156,8 @@ fn main() -> Result<()> {
         pw.set_target(r_targets[i], F::from_canonical_u32(r_vals[i]));
         pw.set_target(g_targets[i], F::from_canonical_u32(g_vals[i]));
         pw.set_target(b_targets[i], F::from_canonical_u32(b_vals[i]));
+        pw.set_target(q_targets[i], F::from_canonical_u32(x_vals[i]));
+        pw.set_target(r_rem_targets[i], F::from_canonical_u32(rem_vals[i] as u32));
     }
 
     let proof = data.prove(pw)?;
@@ -122,11 +167,23 @@ fn main() -> Result<()> {
     // Verification time (equivalent to VIMz "RecursiveSNARK verify")
     let verify_start = Instant::now();
 
+    let mut original_bytes_plain = Vec::new();
     for i in 0..total_pixels {
-        let expected_sum = (r_vals[i] as i32 * 299 + g_vals[i] as i32 * 587 + b_vals[i] as i32 * 114) as u64;
-        assert!(proof.public_inputs[i].0 == expected_sum,
-            "Public input mismatch at pixel {}: expected {}, got {}",
-            i, expected_sum, proof.public_inputs[i].0);
+        original_bytes_plain.push(r_vals[i]);
+        original_bytes_plain.push(g_vals[i]);
+        original_bytes_plain.push(b_vals[i]);
+    }
+    let expected_original_digest = hashing::commit_image_plain(&original_bytes_plain);
+    let expected_output_digest = hashing::commit_image_plain(&x_vals);
+
+    for (i, expected) in expected_original_digest
+        .into_iter()
+        .chain(expected_output_digest)
+        .enumerate()
+    {
+        assert!(proof.public_inputs[i].0 == expected as u64,
+            "Digest word mismatch at index {}: expected {}, got {}",
+            i, expected, proof.public_inputs[i].0);
     }
 
     let res = data.verify(proof);
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use plonky2::field::types::{Field, Sample};
+use plonky2::iop::witness::PartialWitness;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::CircuitConfig;
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use plonky2_ecdsa::curve::curve_types::{Curve, CurveScalar};
+use plonky2_ecdsa::curve::ecdsa::{sign_message, ECDSASecretKey};
+use plonky2_ecdsa::curve::secp256k1::Secp256K1;
+use serde_json::Value;
+use std::fs;
+use std::time::Instant;
+use veritas::ecdsa;
+use veritas::hashing;
+use veritas::lookup;
+
+/// Same crop circuit as `crop-benchmark.rs`, plus a device-provenance check:
+/// the original image's commitment must be signed by a device key that's
+/// exposed as a public input. The public inputs are `(device_pubkey,
+/// out_digest)`: the original image's digest is not registered and is bound
+/// only through the signature. Kept as a standalone example rather than folded into `crop-benchmark.rs`
+/// so the base benchmarks stay independent of the ECDSA gadgets.
+///
+/// The signed digest is `hashing::commit_image`'s BLAKE3-style (not
+/// BLAKE3-conformant, see that module's docs) commitment, so this is a
+/// benchmark stand-in for the signing step, not a drop-in for a real C2PA
+/// pipeline: an actual camera signs a standard hash and couldn't produce a
+/// signature this gadget would verify. This example only round-trips
+/// against a device key it samples itself.
+fn main() -> Result<()> {
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    let json_path = std::env::args().nth(1).expect("Usage: provenance-benchmark <json_file_path>");
+    let json_str = fs::read_to_string(&json_path)?;
+    let data: Value = serde_json::from_str(&json_str)?;
+
+    let original = data["original"].as_array().unwrap();
+    let cropped = data["cropped"].as_array().unwrap();
+    let crop_x = data["crop_x"].as_u64().unwrap() as usize;
+    let crop_y = data["crop_y"].as_u64().unwrap() as usize;
+
+    let mut w_r_vals = Vec::new();
+    for row in original {
+        w_r_vals.push(
+            row.as_array()
+                .unwrap()
+                .iter()
+                .map(|p| p.as_u64().unwrap() as u32)
+                .collect::<Vec<_>>(),
+        );
+    }
+    let mut x_r_vals = Vec::new();
+    for row in cropped {
+        x_r_vals.push(
+            row.as_array()
+                .unwrap()
+                .iter()
+                .map(|p| p.as_u64().unwrap() as u32)
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    let orig_width = w_r_vals[0].len();
+    let crop_width = x_r_vals[0].len();
+    let crop_height = x_r_vals.len();
+    let old_size = w_r_vals.len() * orig_width;
+    let w_r_vals_flat: Vec<u32> = w_r_vals.iter().flatten().copied().collect();
+    let x_r_vals_flat: Vec<u32> = x_r_vals.iter().flatten().copied().collect();
+
+    // Stand in for the camera/device keypair: a real deployment loads this
+    // from a provisioned secret, but for benchmarking we just sample one and
+    // sign the original image's digest with it.
+    let device_sk = ECDSASecretKey::<Secp256K1>(<Secp256K1 as Curve>::ScalarField::rand());
+    let device_pk = (CurveScalar(device_sk.0) * Secp256K1::GENERATOR_PROJECTIVE).to_affine();
+    let original_digest_plain = hashing::commit_image_plain(&w_r_vals_flat);
+    let msg = plonky2_ecdsa::curve::secp256k1::Secp256K1Scalar::from_noncanonical_biguint(
+        original_digest_plain
+            .iter()
+            .rev()
+            .fold(num::BigUint::from(0u32), |acc, &w| (acc << 32) + w),
+    );
+    let signature = sign_message(msg, device_sk);
+
+    let circuit_start = Instant::now();
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+    let mut pw = PartialWitness::new();
+
+    let byte_table = lookup::add_byte_range_table(&mut builder);
+    let mut orig_targets = Vec::new();
+    for _ in 0..old_size {
+        let t = builder.add_virtual_target();
+        lookup::constrain_byte(&mut builder, byte_table, t);
+        orig_targets.push(t);
+    }
+
+    let mut w_r_targets = Vec::new();
+    for i in 0..crop_height {
+        for j in 0..crop_width {
+            let orig_row = crop_y + i;
+            let orig_col = crop_x + j;
+            w_r_targets.push(orig_targets[orig_row * orig_width + orig_col]);
+        }
+    }
+
+    let original_digest = hashing::commit_image(&mut builder, &orig_targets);
+    let output_digest = hashing::commit_image(&mut builder, &w_r_targets);
+
+    let provenance = ecdsa::verify_provenance(&mut builder, original_digest);
+    for word in output_digest {
+        builder.register_public_input(word);
+    }
+
+    let circuit_data = builder.build::<C>();
+    let circuit_time = circuit_start.elapsed();
+
+    let num_gates = circuit_data.common.gates.len();
+    let num_variables = old_size;
+
+    println!("Circuit build took: {:.9}s", circuit_time.as_secs_f64());
+    println!("Number of constraints: {}", num_gates);
+    println!("Number of variables: {}", num_variables);
+
+    let proof_start = Instant::now();
+    for i in 0..old_size {
+        pw.set_target(orig_targets[i], F::from_canonical_u32(w_r_vals_flat[i]));
+    }
+    ecdsa::set_provenance_witness(&mut pw, &provenance, &device_pk, &signature);
+
+    let proof = circuit_data.prove(pw)?;
+    let proof_time = proof_start.elapsed();
+    println!("Proof generation took: {:.9}s", proof_time.as_secs_f64());
+
+    let verify_start = Instant::now();
+    let expected_output_digest = hashing::commit_image_plain(&x_r_vals_flat);
+    let output_offset = proof.public_inputs.len() - expected_output_digest.len();
+    for (i, expected) in expected_output_digest.into_iter().enumerate() {
+        assert!(
+            proof.public_inputs[output_offset + i].0 == expected as u64,
+            "Output digest word mismatch at index {}",
+            i
+        );
+    }
+
+    let res = circuit_data.verify(proof);
+    let _ = res?;
+    let verify_time = verify_start.elapsed();
+    println!("Verification took: {:.9}ms", verify_time.as_secs_f64() * 1000.0);
+
+    Ok(())
+}
@@ -7,6 +7,8 @@ use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
 use serde_json::Value;
 use std::fs;
 use std::time::Instant;
+use veritas::hashing;
+use veritas::lookup;
 
 fn get_positions(i: usize, j: usize, w_orig: usize, h_orig: usize, w_new: usize, h_new: usize) -> (usize, usize, usize, usize) {
     let x_l = if w_new > 1 { (w_orig - 1) * j / (w_new - 1) } else { 0 };
@@ -108,25 +110,55 @@ fn main() -> Result<()> {
 
     let mut pw = PartialWitness::new();
 
-    let mut w_r_targets = Vec::new();
+    // Wire the full original image once; each output pixel's corner targets
+    // reference into this grid instead of declaring fresh virtual targets,
+    // so the same original pixel is the same target everywhere it's used.
+    let byte_table = lookup::add_byte_range_table(&mut builder);
+    let mut orig_targets = Vec::new();
+    for _ in 0..H_ORIG {
+        let mut row = Vec::new();
+        for _ in 0..W_ORIG {
+            let t = builder.add_virtual_target();
+            lookup::constrain_byte(&mut builder, byte_table, t);
+            row.push(t);
+        }
+        orig_targets.push(row);
+    }
+
+    // The weighted sum `s` isn't a byte (it's bounded by `255 * denom`, which
+    // exceeds 255 for any non-trivial resize), so it can't be committed
+    // directly — `commit_image` assumes byte-valued inputs. Instead, as with
+    // gray/blur, constrain the true output byte `new` and a remainder: `s =
+    // new*denom + rem`, `new` a valid byte via the lookup table, `rem` the
+    // rounding error of round-to-nearest (round-half-up for positive
+    // numbers, matching `f64::round`) bounded to exactly `[-shift, denom -
+    // shift)`, a window of width `denom` — not `2*denom`, which would admit
+    // two adjacent `new` values for the same `s` and let a prover forge an
+    // off-by-one output. `denom` is the same for every output pixel in one
+    // resize, so its bit-width is computed once.
+    let denom = if W_NEW > 1 && H_NEW > 1 { (W_NEW - 1) * (H_NEW - 1) } else { 1 };
+    let shift = denom / 2;
+    // True range of `rem + shift` is `[0, denom)`; `rem_bits` is the
+    // smallest bit-width that fits it (at least 1, so the double range
+    // check below is well-formed even when `denom == 1`).
+    let rem_bits = (usize::BITS - (denom.max(2) - 1).leading_zeros()) as usize;
+    let rem_slack = (1usize << rem_bits) - denom;
+    let shift_target = builder.constant(F::from_canonical_u64(shift as u64));
+
+    let mut new_targets = Vec::new();
 
     for i in 0..H_NEW {
         for j in 0..W_NEW {
-            let a = builder.add_virtual_target();
-            let b = builder.add_virtual_target();
-            let c = builder.add_virtual_target();
-            let d = builder.add_virtual_target();
-            
-            w_r_targets.push(a);
-            w_r_targets.push(b);
-            w_r_targets.push(c);
-            w_r_targets.push(d);
+            let (x_l, y_l, x_h, y_h) = get_positions(i, j, W_ORIG, H_ORIG, W_NEW, H_NEW);
+            let a = orig_targets[y_l][x_l];
+            let b = orig_targets[y_l][x_h];
+            let c = orig_targets[y_h][x_l];
+            let d = orig_targets[y_h][x_h];
 
             let (x_ratio_weighted, y_ratio_weighted) = get_ratios(i, j, W_ORIG, H_ORIG, W_NEW, H_NEW);
 
             let mut all = Vec::new();
 
-            let _denom = if W_NEW > 1 && H_NEW > 1 { (W_NEW - 1) * (H_NEW - 1) } else { 1 };
             let a_const = ((W_NEW - 1 - x_ratio_weighted) * (H_NEW - 1 - y_ratio_weighted)) as u32;
             let b_const = (x_ratio_weighted * (H_NEW - 1 - y_ratio_weighted)) as u32;
             let c_const = (y_ratio_weighted * (W_NEW - 1 - x_ratio_weighted)) as u32;
@@ -137,8 +169,38 @@ fn main() -> Result<()> {
             all.push(builder.mul_const(F::from_canonical_u32(d_const), d));
 
             let s = builder.add_many(all);
-            builder.register_public_input(s);
-        }         
+
+            let new = builder.add_virtual_target();
+            lookup::constrain_byte(&mut builder, byte_table, new);
+
+            // `rem_shifted = rem + shift` is always nonnegative, so it's the
+            // target we actually witness and range-check; `rem` itself is
+            // derived from it in-circuit.
+            let rem_shifted = builder.add_virtual_target();
+            let rem = builder.sub(rem_shifted, shift_target);
+            let new_times_denom = builder.mul_const(F::from_canonical_u32(denom as u32), new);
+            let reconstructed = builder.add(new_times_denom, rem);
+            builder.connect(s, reconstructed);
+
+            // As with the grayscale division, one range_check alone would
+            // admit rem_shifted up to 2^rem_bits, wider than the true
+            // [0, denom) range; the shifted second check rules that slack out.
+            builder.range_check(rem_shifted, rem_bits);
+            let rem_shifted_top = builder.add_const(rem_shifted, F::from_canonical_u32(rem_slack as u32));
+            builder.range_check(rem_shifted_top, rem_bits);
+
+            new_targets.push((new, rem_shifted));
+        }
+    }
+
+    // Commit to the original image and the true resized byte output
+    // instead of leaking every pixel as a public input.
+    let orig_targets_flat: Vec<_> = orig_targets.iter().flatten().copied().collect();
+    let output_targets_flat: Vec<_> = new_targets.iter().map(|&(new, _)| new).collect();
+    let original_digest = hashing::commit_image(&mut builder, &orig_targets_flat);
+    let output_digest = hashing::commit_image(&mut builder, &output_targets_flat);
+    for word in original_digest.into_iter().chain(output_digest) {
+        builder.register_public_input(word);
     }
 
     let data = builder.build::<C>();
@@ -146,7 +208,7 @@ fn main() -> Result<()> {
 
     // Get circuit statistics
     let num_gates = data.common.gates.len();
-    let num_variables = H_NEW * W_NEW * 4; // 4 corner pixels per output pixel
+    let num_variables = H_ORIG * W_ORIG; // the original image, shared across all output pixels' corners
 
     // Output metrics in VIMz-compatible format
     println!("Circuit build took: {:.9}s", circuit_time.as_secs_f64());
@@ -156,14 +218,19 @@ fn main() -> Result<()> {
     // Proof generation time (equivalent to VIMz "RecursiveSNARK creation")
     let proof_start = Instant::now();
 
+    for i in 0..H_ORIG {
+        for j in 0..W_ORIG {
+            pw.set_target(orig_targets[i][j], F::from_canonical_u32(w_r_vals[i][j]));
+        }
+    }
+
     for i in 0..H_NEW {
         for j in 0..W_NEW {
-            let (x_l, y_l, x_h, y_h) = get_positions(i, j, W_ORIG, H_ORIG, W_NEW, H_NEW);
-
-            pw.set_target(w_r_targets[4 * i * W_NEW + 4 * j], F::from_canonical_u32(w_r_vals[y_l][x_l]));
-            pw.set_target(w_r_targets[4 * i * W_NEW + 4 * j + 1], F::from_canonical_u32(w_r_vals[y_l][x_h]));
-            pw.set_target(w_r_targets[4 * i * W_NEW + 4 * j + 2], F::from_canonical_u32(w_r_vals[y_h][x_l]));
-            pw.set_target(w_r_targets[4 * i * W_NEW + 4 * j + 3], F::from_canonical_u32(w_r_vals[y_h][x_h]));
+            let idx = i * W_NEW + j;
+            let (new, rem_shifted) = new_targets[idx];
+            pw.set_target(new, F::from_canonical_u32(x_r_vals[i][j]));
+            let rem_shifted_val = (rem_r_vals[i][j] + shift as i64) as u32;
+            pw.set_target(rem_shifted, F::from_canonical_u32(rem_shifted_val));
         }
     }
 
@@ -174,15 +241,19 @@ fn main() -> Result<()> {
     // Verification time (equivalent to VIMz "RecursiveSNARK verify")
     let verify_start = Instant::now();
 
-    let denom = if W_NEW > 1 && H_NEW > 1 { (W_NEW - 1) * (H_NEW - 1) } else { 1 };
-
-    for i in 0..H_NEW {
-        for j in 0..W_NEW {
-            let x = (x_r_vals[i][j] as usize * denom) as i64 + rem_r_vals[i][j];
-            assert!(x as u64 == proof.public_inputs[W_NEW * i + j].0,
-                "Public input mismatch at ({}, {}): expected {}, got {}",
-                i, j, x, proof.public_inputs[W_NEW * i + j].0);
-        }
+    let orig_vals_flat: Vec<u32> = w_r_vals.iter().flatten().copied().collect();
+    let output_vals_flat: Vec<u32> = x_r_vals.iter().flatten().copied().collect();
+
+    let expected_original_digest = hashing::commit_image_plain(&orig_vals_flat);
+    let expected_output_digest = hashing::commit_image_plain(&output_vals_flat);
+    for (i, expected) in expected_original_digest
+        .into_iter()
+        .chain(expected_output_digest)
+        .enumerate()
+    {
+        assert!(proof.public_inputs[i].0 == expected as u64,
+            "Digest word mismatch at index {}: expected {}, got {}",
+            i, expected, proof.public_inputs[i].0);
     }
 
     let res = data.verify(proof);
@@ -0,0 +1,55 @@
+use anyhow::{ensure, Result};
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use plonky2::plonk::verifier::verify;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+use veritas::serialize;
+
+/// Standalone detached verifier: loads a proof bundle written by
+/// `serialize::write_proof_bundle` (e.g. by `crop-benchmark.rs`) plus the
+/// expected public digests, and checks the proof without ever running a
+/// prover — mirroring how a SNARK-verifier SDK separates proving from
+/// verification so a proof can be archived alongside an edited image and
+/// checked by anyone offline.
+fn main() -> Result<()> {
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+
+    let mut args = std::env::args().skip(1);
+    let proof_dir = args.next().expect("Usage: verify <proof_dir> <digests.json>");
+    let digests_path = args.next().expect("Usage: verify <proof_dir> <digests.json>");
+
+    let bundle = serialize::read_proof_bundle::<<C as GenericConfig<D>>::F, C, D>(Path::new(&proof_dir))?;
+
+    let digests_str = fs::read_to_string(&digests_path)?;
+    let digests: Value = serde_json::from_str(&digests_str)?;
+    let original_digest: Vec<u64> = digests["original_digest"].as_array().unwrap().iter().map(|v| v.as_u64().unwrap()).collect();
+    let output_digest: Vec<u64> = digests["output_digest"].as_array().unwrap().iter().map(|v| v.as_u64().unwrap()).collect();
+    let expected: Vec<u64> = original_digest.into_iter().chain(output_digest).collect();
+
+    ensure!(
+        bundle.proof.public_inputs.len() == expected.len(),
+        "public input count mismatch: proof has {}, expected digests file has {}",
+        bundle.proof.public_inputs.len(),
+        expected.len()
+    );
+    for (i, &want) in expected.iter().enumerate() {
+        ensure!(
+            bundle.proof.public_inputs[i].0 == want,
+            "Digest word mismatch at index {}: expected {}, got {}",
+            i,
+            want,
+            bundle.proof.public_inputs[i].0
+        );
+    }
+
+    let verify_start = Instant::now();
+    verify::<_, C, D>(bundle.proof.clone(), &bundle.verifier_only, &bundle.common)?;
+    let verify_time = verify_start.elapsed();
+    println!("Verification took: {:.9}ms", verify_time.as_secs_f64() * 1000.0);
+    println!("Proof OK: public digests match and the proof is valid.");
+
+    Ok(())
+}
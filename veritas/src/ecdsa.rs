@@ -0,0 +1,94 @@
+//! Optional camera/device provenance: verify a secp256k1 ECDSA signature
+//! over an in-circuit image commitment.
+//!
+//! This lets a verifier learn "this edit was applied to an image signed by
+//! trusted key K" (C2PA-style provenance) without ever seeing the image.
+//! It's a thin circuit-building layer on top of the `plonky2_ecdsa` / `u32`
+//! nonnative-field gadgets (the `ecdsa` workspace member also
+//! used by plonky2's own ECDSA tests) — those crates implement the
+//! secp256k1 base/scalar field and curve arithmetic; this module only packs
+//! our [`crate::hashing`] digest into the nonnative message format they
+//! expect and wires up the verification gate.
+//!
+//! Usage is optional: a transform only needs this if it wants to prove
+//! provenance of the *original* image. Its one user,
+//! `examples/provenance-benchmark.rs`, registers `(device_pubkey,
+//! out_digest)` as public inputs; the original image's digest stays private
+//! and is bound only through the signature.
+
+use plonky2::field::extension::Extendable;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2_ecdsa::curve::ecdsa::{ECDSAPublicKey, ECDSASignature};
+use plonky2_ecdsa::curve::secp256k1::Secp256K1;
+use plonky2_ecdsa::gadgets::curve::{AffinePointTarget, CircuitBuilderCurve};
+use plonky2_ecdsa::gadgets::ecdsa::{verify_message_circuit, ECDSAPublicKeyTarget, ECDSASignatureTarget};
+use plonky2_ecdsa::gadgets::nonnative::{CircuitBuilderNonNative, NonNativeTarget};
+
+/// A device public key and ECDSA signature, wired as circuit targets. The
+/// plaintext values are not stored here; [`set_provenance_witness`] takes
+/// them separately.
+pub struct ProvenanceTargets {
+    pub pubkey: ECDSAPublicKeyTarget<Secp256K1>,
+    pub signature: ECDSASignatureTarget<Secp256K1>,
+}
+
+/// Packs an 8-word BLAKE3 digest (from [`crate::hashing::commit_image`])
+/// into the 256-bit nonnative scalar that `verify_message_circuit` expects
+/// as the signed message: `word[0]` is the low 32 bits, `word[7]` the high.
+fn digest_to_nonnative_message<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    digest: [Target; 8],
+) -> NonNativeTarget<plonky2_ecdsa::curve::secp256k1::Secp256K1Scalar> {
+    let mut acc = builder.zero_u32();
+    for &word in digest.iter().rev() {
+        let word_u32 = builder.u32_from_target_unsafe(word);
+        acc = builder.mul_u32_and_shift(acc, 1 << 32, word_u32); // acc = acc << 32 | word
+    }
+    builder.biguint_to_nonnative(&acc)
+}
+
+/// Adds the ECDSA provenance check: asserts `signature` is a valid
+/// secp256k1 signature by `pubkey` over `original_digest`. Registers
+/// `pubkey` as public inputs (every 32-bit limb of `x`, then of `y`: 8 per
+/// coordinate) so verifiers can check which device is vouched for; the
+/// signature and the original pixels stay witness-only.
+pub fn verify_provenance<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    original_digest: [Target; 8],
+) -> ProvenanceTargets {
+    let pubkey = ECDSAPublicKeyTarget(AffinePointTarget {
+        x: builder.add_virtual_nonnative_target(),
+        y: builder.add_virtual_nonnative_target(),
+    });
+    let signature = ECDSASignatureTarget {
+        r: builder.add_virtual_nonnative_target(),
+        s: builder.add_virtual_nonnative_target(),
+    };
+
+    let message = digest_to_nonnative_message(builder, original_digest);
+    verify_message_circuit(builder, message, signature.clone(), pubkey.clone());
+
+    for limb in pubkey.0.x.value.limbs.iter().chain(pubkey.0.y.value.limbs.iter()) {
+        builder.register_public_input(limb.0);
+    }
+
+    ProvenanceTargets { pubkey, signature }
+}
+
+/// Fills in the witness for a [`ProvenanceTargets`] with a concrete device
+/// public key and signature.
+pub fn set_provenance_witness<F: RichField + Extendable<D>, const D: usize>(
+    pw: &mut PartialWitness<F>,
+    targets: &ProvenanceTargets,
+    pubkey: &ECDSAPublicKey<Secp256K1>,
+    signature: &ECDSASignature<Secp256K1>,
+) {
+    pw.set_biguint_target(&targets.pubkey.0.x.value, &pubkey.0.x.to_canonical_biguint());
+    pw.set_biguint_target(&targets.pubkey.0.y.value, &pubkey.0.y.to_canonical_biguint());
+    pw.set_biguint_target(&targets.signature.r.value, &signature.r.to_canonical_biguint());
+    pw.set_biguint_target(&targets.signature.s.value, &signature.s.to_canonical_biguint());
+}
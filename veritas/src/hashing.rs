@@ -0,0 +1,339 @@
+//! In-circuit image commitments via a BLAKE3-style compression function.
+//!
+//! Registering every pixel as a public input leaks the whole image and makes
+//! proof size linear in image size. Instead we pack the pixel targets into
+//! Goldilocks field elements (4 bytes little-endian per word), absorb them
+//! 64 bytes (16 words) at a time through a compression function built on
+//! BLAKE3's mixing round (`g`), IV, and message permutation, and register
+//! only the resulting 8-word chaining value as the public commitment to the
+//! image.
+//!
+//! This is a from-scratch in-circuit construction built on top of plonky2's
+//! bit-decomposition gadgets (`split_le` / `le_sum`), not a wrapper around a
+//! dedicated BLAKE3 gate — and it is **not** BLAKE3-conformant: the block
+//! counter here increments per 64-byte block rather than per 1024-byte
+//! chunk, and `CHUNK_START`/`CHUNK_END` are never distinguished from each
+//! other (only the final block carries flags, `CHUNK_START | CHUNK_END |
+//! ROOT` together; every other block has none). The construction is internally
+//! consistent — the in-circuit and native (`_plain`) paths always agree, so
+//! it's sound as a commitment — but its digests will not match a real
+//! BLAKE3 implementation's output.
+
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::target::{BoolTarget, Target};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+/// Initialization vector borrowed from BLAKE3/BLAKE2s (first 8 words of the
+/// SHA-256 IV).
+pub const IV: [u32; 8] = [
+    0x6A09_E667,
+    0xBB67_AE85,
+    0x3C6E_F372,
+    0xA54F_F53A,
+    0x510E_527F,
+    0x9B05_688C,
+    0x1F83_D9AB,
+    0x5BE0_CD19,
+];
+
+/// Message word permutation applied before each round after the first,
+/// per the BLAKE3 specification.
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+const ROUNDS: usize = 7;
+
+/// A 256-bit image commitment as 8 field-element words, each constrained to
+/// fit in 32 bits.
+pub type Digest = [Target; 8];
+
+/// XOR of two bits, expressed as an arithmetic constraint (`a + b - 2ab`)
+/// rather than a native boolean gate, since plonky2's base config has no
+/// dedicated XOR gate.
+fn bool_xor<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    a: BoolTarget,
+    b: BoolTarget,
+) -> BoolTarget {
+    let sum = builder.add(a.target, b.target);
+    let prod = builder.mul(a.target, b.target);
+    let two_prod = builder.add(prod, prod);
+    let xor = builder.sub(sum, two_prod);
+    BoolTarget::new_unsafe(xor)
+}
+
+/// `(a ^ b) mod 2^32`, via bit decomposition.
+fn xor_u32<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    a: Target,
+    b: Target,
+) -> Target {
+    let a_bits = builder.split_le(a, 32);
+    let b_bits = builder.split_le(b, 32);
+    let xor_bits: Vec<BoolTarget> = a_bits
+        .into_iter()
+        .zip(b_bits)
+        .map(|(x, y)| bool_xor(builder, x, y))
+        .collect();
+    builder.le_sum(xor_bits.into_iter())
+}
+
+/// `(a + b) mod 2^32`, dropping the carry bit produced by the 33-bit sum.
+fn add_u32<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    a: Target,
+    b: Target,
+) -> Target {
+    let sum = builder.add(a, b);
+    let bits = builder.split_le(sum, 33);
+    builder.le_sum(bits[0..32].iter().copied())
+}
+
+/// Right-rotate a 32-bit word by `n` bits.
+fn rotr_u32<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    a: Target,
+    n: usize,
+) -> Target {
+    let bits = builder.split_le(a, 32);
+    let rotated = (0..32).map(|i| bits[(i + n) % 32]);
+    builder.le_sum(rotated)
+}
+
+/// One quarter-round, as specified by BLAKE3's `g` function: two
+/// add/xor/rotate mixing steps over the `(a, b, c, d)` state words,
+/// absorbing message words `mx` and `my`.
+fn g<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    state: &mut [Target; 16],
+    a: usize,
+    b: usize,
+    c: usize,
+    d: usize,
+    mx: Target,
+    my: Target,
+) {
+    state[a] = add_u32(builder, state[a], state[b]);
+    state[a] = add_u32(builder, state[a], mx);
+    state[d] = xor_u32(builder, state[d], state[a]);
+    state[d] = rotr_u32(builder, state[d], 16);
+
+    state[c] = add_u32(builder, state[c], state[d]);
+    state[b] = xor_u32(builder, state[b], state[c]);
+    state[b] = rotr_u32(builder, state[b], 12);
+
+    state[a] = add_u32(builder, state[a], state[b]);
+    state[a] = add_u32(builder, state[a], my);
+    state[d] = xor_u32(builder, state[d], state[a]);
+    state[d] = rotr_u32(builder, state[d], 8);
+
+    state[c] = add_u32(builder, state[c], state[d]);
+    state[b] = xor_u32(builder, state[b], state[c]);
+    state[b] = rotr_u32(builder, state[b], 7);
+}
+
+/// The compression function: mixes a 16-word state (8 chaining-value words,
+/// 4 IV words, a 2-word counter and 2 flag/length words) through `ROUNDS`
+/// rounds of `g`, permuting the message schedule between rounds like
+/// BLAKE3 does, then folds the state back down to an 8-word chaining value.
+/// Unlike real BLAKE3, `counter` here is a running count of 64-byte blocks
+/// rather than 1024-byte chunks — see the module docs.
+fn compress<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    chaining_value: &Digest,
+    block_words: &[Target; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> Digest {
+    let mut state = [Target::default(); 16];
+    state[0..8].copy_from_slice(chaining_value);
+    for i in 0..4 {
+        state[8 + i] = builder.constant(F::from_canonical_u32(IV[i]));
+    }
+    state[12] = builder.constant(F::from_canonical_u32(counter as u32));
+    state[13] = builder.constant(F::from_canonical_u32((counter >> 32) as u32));
+    state[14] = builder.constant(F::from_canonical_u32(block_len));
+    state[15] = builder.constant(F::from_canonical_u32(flags));
+
+    let mut msg = *block_words;
+    for round in 0..ROUNDS {
+        g(builder, &mut state, 0, 4, 8, 12, msg[0], msg[1]);
+        g(builder, &mut state, 1, 5, 9, 13, msg[2], msg[3]);
+        g(builder, &mut state, 2, 6, 10, 14, msg[4], msg[5]);
+        g(builder, &mut state, 3, 7, 11, 15, msg[6], msg[7]);
+
+        g(builder, &mut state, 0, 5, 10, 15, msg[8], msg[9]);
+        g(builder, &mut state, 1, 6, 11, 12, msg[10], msg[11]);
+        g(builder, &mut state, 2, 7, 8, 13, msg[12], msg[13]);
+        g(builder, &mut state, 3, 4, 9, 14, msg[14], msg[15]);
+
+        if round + 1 < ROUNDS {
+            let permuted = MSG_PERMUTATION.map(|i| msg[i]);
+            msg = permuted;
+        }
+    }
+
+    let mut cv = [Target::default(); 8];
+    for i in 0..8 {
+        cv[i] = xor_u32(builder, state[i], state[8 + i]);
+    }
+    cv
+}
+
+/// Packs 4 consecutive byte-valued targets into one little-endian 32-bit
+/// word: `word = b0 + b1*2^8 + b2*2^16 + b3*2^24`. Missing trailing bytes
+/// (when the input isn't a multiple of 4) are padded with zero.
+fn pack_bytes_to_words<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    bytes: &[Target],
+) -> Vec<Target> {
+    bytes
+        .chunks(4)
+        .map(|chunk| {
+            let mut acc = builder.zero();
+            for (i, &byte) in chunk.iter().enumerate() {
+                let shifted = builder.mul_const(F::from_canonical_u64(1 << (8 * i)), byte);
+                acc = builder.add(acc, shifted);
+            }
+            acc
+        })
+        .collect()
+}
+
+/// Commits to an image by packing its pixel targets into message blocks and
+/// absorbing them through [`compress`], returning the final 8-word chaining
+/// value. Callers register the returned targets as public inputs instead of
+/// the raw pixel targets.
+pub fn commit_image<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    pixel_targets: &[Target],
+) -> Digest {
+    let start_cv: Digest = IV.map(|w| builder.constant(F::from_canonical_u32(w)));
+    commit_blocks(builder, start_cv, pixel_targets, 0, true).0
+}
+
+/// Like [`commit_image`], but starts absorbing from a caller-supplied
+/// chaining value and block counter instead of the BLAKE3 IV, and the
+/// caller says whether this call's last block is the overall last block
+/// (controls the ROOT flag). This lets independent circuits each commit to
+/// one region of the same image and chain their digests into a single
+/// running commitment — see the tiled proving in `blur-benchmark.rs`.
+/// Returns the new chaining value and the block counter to pass to the next
+/// call.
+pub fn commit_blocks<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    start_cv: Digest,
+    pixel_targets: &[Target],
+    block_offset: u64,
+    is_last_region: bool,
+) -> (Digest, u64) {
+    let words = pack_bytes_to_words(builder, pixel_targets);
+    let zero = builder.zero();
+    let mut cv = start_cv;
+
+    let num_blocks = words.len().div_ceil(16).max(1);
+    for block_index in 0..num_blocks {
+        let start = block_index * 16;
+        let mut block_words = [zero; 16];
+        for i in 0..16 {
+            if let Some(&w) = words.get(start + i) {
+                block_words[i] = w;
+            }
+        }
+        let is_last = is_last_region && block_index + 1 == num_blocks;
+        let flags = if is_last { 0x0B } else { 0x00 }; // 0x0B on the only/last block, 0x00 otherwise — not a real BLAKE3 flag schedule, see module docs
+        cv = compress(builder, &cv, &block_words, block_offset + block_index as u64, 64, flags);
+    }
+
+    (cv, block_offset + num_blocks as u64)
+}
+
+/// Native (out-of-circuit) mirror of [`commit_image`], used by benchmark
+/// binaries to compute the expected digest from plaintext pixel bytes so it
+/// can be checked against the proof's public inputs before calling `verify`.
+pub fn commit_image_plain(pixel_bytes: &[u32]) -> [u32; 8] {
+    commit_blocks_plain(IV, pixel_bytes, 0, true).0
+}
+
+/// Native mirror of [`commit_blocks`]: absorbs `pixel_bytes` starting from
+/// `start_cv`/`block_offset`, returning the new chaining value and block
+/// counter.
+pub fn commit_blocks_plain(start_cv: [u32; 8], pixel_bytes: &[u32], block_offset: u64, is_last_region: bool) -> ([u32; 8], u64) {
+    let words: Vec<u32> = pixel_bytes
+        .chunks(4)
+        .map(|chunk| {
+            let mut word = 0u32;
+            for (i, &byte) in chunk.iter().enumerate() {
+                word |= byte << (8 * i);
+            }
+            word
+        })
+        .collect();
+
+    let mut cv = start_cv;
+    let num_blocks = words.len().div_ceil(16).max(1);
+    for block_index in 0..num_blocks {
+        let start = block_index * 16;
+        let mut block_words = [0u32; 16];
+        for i in 0..16 {
+            if let Some(&w) = words.get(start + i) {
+                block_words[i] = w;
+            }
+        }
+        let is_last = is_last_region && block_index + 1 == num_blocks;
+        let flags = if is_last { 0x0B } else { 0x00 };
+        cv = compress_plain(&cv, &block_words, block_offset + block_index as u64, 64, flags);
+    }
+    (cv, block_offset + num_blocks as u64)
+}
+
+fn compress_plain(
+    chaining_value: &[u32; 8],
+    block_words: &[u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 8] {
+    let mut state = [0u32; 16];
+    state[0..8].copy_from_slice(chaining_value);
+    state[8..12].copy_from_slice(&IV[0..4]);
+    state[12] = counter as u32;
+    state[13] = (counter >> 32) as u32;
+    state[14] = block_len;
+    state[15] = flags;
+
+    fn g_plain(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, mx: u32, my: u32) {
+        state[a] = state[a].wrapping_add(state[b]).wrapping_add(mx);
+        state[d] = (state[d] ^ state[a]).rotate_right(16);
+        state[c] = state[c].wrapping_add(state[d]);
+        state[b] = (state[b] ^ state[c]).rotate_right(12);
+        state[a] = state[a].wrapping_add(state[b]).wrapping_add(my);
+        state[d] = (state[d] ^ state[a]).rotate_right(8);
+        state[c] = state[c].wrapping_add(state[d]);
+        state[b] = (state[b] ^ state[c]).rotate_right(7);
+    }
+
+    let mut msg = *block_words;
+    for round in 0..ROUNDS {
+        g_plain(&mut state, 0, 4, 8, 12, msg[0], msg[1]);
+        g_plain(&mut state, 1, 5, 9, 13, msg[2], msg[3]);
+        g_plain(&mut state, 2, 6, 10, 14, msg[4], msg[5]);
+        g_plain(&mut state, 3, 7, 11, 15, msg[6], msg[7]);
+
+        g_plain(&mut state, 0, 5, 10, 15, msg[8], msg[9]);
+        g_plain(&mut state, 1, 6, 11, 12, msg[10], msg[11]);
+        g_plain(&mut state, 2, 7, 8, 13, msg[12], msg[13]);
+        g_plain(&mut state, 3, 4, 9, 14, msg[14], msg[15]);
+
+        if round + 1 < ROUNDS {
+            msg = MSG_PERMUTATION.map(|i| msg[i]);
+        }
+    }
+
+    let mut cv = [0u32; 8];
+    for i in 0..8 {
+        cv[i] = state[i] ^ state[8 + i];
+    }
+    cv
+}
@@ -0,0 +1,199 @@
+//! Recursive (IVC/folding-style) proof aggregation over a chain of steps.
+//!
+//! Each transform in an edit pipeline (crop, resize, blur, gray) is proved
+//! independently as a [`crate::steps::StepCircuit`] — a "step", in IVC
+//! terms, whose public inputs are `(in_digest, out_digest)`. The builder
+//! functions in [`crate::steps`] (`build_crop_step`, `build_resize_step`,
+//! `build_blur_step`, `build_gray_step`) are the step implementations for
+//! the four transforms. [`fold_steps`] chains them together: it proves step
+//! 0, then for every subsequent step builds a small recursion circuit that
+//! verifies the previous (folded) proof and the new step's proof, and
+//! asserts `previous.out_digest == step.in_digest`. The recursion circuit's
+//! own public inputs are `(first_in_digest, step.out_digest)`, so folding
+//! the whole chain yields one proof whose public IO is
+//! `(first_in_digest, last_out_digest)` and whose verification cost doesn't
+//! grow with the number of steps.
+
+use crate::steps::StepCircuit;
+use anyhow::Result;
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::{CircuitConfig, CircuitData};
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
+use plonky2::plonk::proof::ProofWithPublicInputs;
+use rayon::prelude::*;
+
+/// The result of folding a chain of steps: the aggregator circuit for the
+/// final fold (needed to verify the proof) and the proof itself. Its 16
+/// public inputs are the first step's 8-word `in_digest` followed by the
+/// last step's 8-word `out_digest`.
+pub struct FoldedProof<F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    pub data: CircuitData<F, C, D>,
+    pub proof: ProofWithPublicInputs<F, C, D>,
+}
+
+/// Proves and folds an ordered chain of steps into one succinct proof.
+///
+/// Panics if `steps` is empty, or if consecutive steps' digests don't chain
+/// (`steps[k].out_digest != steps[k + 1].in_digest`) — callers should check
+/// that before spending the time to prove.
+pub fn fold_steps<F, C, const D: usize>(steps: Vec<StepCircuit<F, C, D>>) -> Result<FoldedProof<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F> + 'static,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    let mut steps = steps.into_iter();
+    let first = steps.next().expect("pipeline needs at least one step");
+
+    let mut folded_data = first.data;
+    let mut folded_proof = folded_data.prove(first.witness)?;
+
+    for step in steps {
+        assert_eq!(
+            folded_proof.public_inputs[8..16]
+                .iter()
+                .map(|v| v.to_canonical_u64())
+                .collect::<Vec<_>>(),
+            step.in_digest.iter().map(|&w| w as u64).collect::<Vec<_>>(),
+            "step digests don't chain: previous out_digest != next step's in_digest"
+        );
+
+        let step_proof = step.data.prove(step.witness)?;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let folded_proof_t = builder.add_virtual_proof_with_pis(&folded_data.common);
+        // `constant_verifier_data` bakes the verifying key in as circuit
+        // constants rather than a free witness value, so a prover can't
+        // substitute a different (possibly attacker-controlled) circuit at
+        // proving time — `verify_proof` only accepts a proof for exactly
+        // this `folded_data`/`step.data`.
+        let folded_verifier_t = builder.constant_verifier_data(&folded_data.verifier_only);
+        builder.verify_proof::<C>(&folded_proof_t, &folded_verifier_t, &folded_data.common);
+
+        let step_proof_t = builder.add_virtual_proof_with_pis(&step.data.common);
+        let step_verifier_t = builder.constant_verifier_data(&step.data.verifier_only);
+        builder.verify_proof::<C>(&step_proof_t, &step_verifier_t, &step.data.common);
+
+        // Chain: previous fold's out_digest == this step's in_digest.
+        for i in 0..8 {
+            builder.connect(folded_proof_t.public_inputs[8 + i], step_proof_t.public_inputs[i]);
+        }
+
+        // New public inputs: (first_in_digest, this step's out_digest).
+        for i in 0..8 {
+            builder.register_public_input(folded_proof_t.public_inputs[i]);
+        }
+        for i in 0..8 {
+            builder.register_public_input(step_proof_t.public_inputs[8 + i]);
+        }
+
+        let aggregated_data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_proof_with_pis_target(&folded_proof_t, &folded_proof);
+        pw.set_proof_with_pis_target(&step_proof_t, &step_proof);
+
+        folded_proof = aggregated_data.prove(pw)?;
+        folded_data = aggregated_data;
+    }
+
+    Ok(FoldedProof {
+        data: folded_data,
+        proof: folded_proof,
+    })
+}
+
+/// Like [`fold_steps`], but proves every step's circuit concurrently on a
+/// rayon thread pool before running the (inherently sequential) recursive
+/// merge. Each step still costs one `verify_proof` gadget in the merge
+/// circuit; what's parallelized is the expensive per-step witness
+/// generation and FRI proving, not the merge itself. Used by
+/// `blur-benchmark.rs` to prove independent row tiles of a large image
+/// concurrently instead of one oversized single-threaded circuit.
+///
+/// `num_threads` sizes a scoped rayon pool for this call only, so it
+/// doesn't affect unrelated parallelism elsewhere in the process.
+pub fn fold_steps_parallel<F, C, const D: usize>(
+    steps: Vec<StepCircuit<F, C, D>>,
+    num_threads: usize,
+) -> Result<FoldedProof<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F> + 'static,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .expect("failed to build tile-proving thread pool");
+
+    let mut proved: Vec<(CircuitData<F, C, D>, ProofWithPublicInputs<F, C, D>, [u32; 8], [u32; 8])> = pool.install(|| {
+        steps
+            .into_par_iter()
+            .map(|step| {
+                let in_digest = step.in_digest;
+                let out_digest = step.out_digest;
+                let proof = step.data.prove(step.witness).expect("tile proof generation failed");
+                (step.data, proof, in_digest, out_digest)
+            })
+            .collect()
+    });
+
+    let (mut folded_data, mut folded_proof, _, _) = proved.remove(0);
+
+    for (step_data, step_proof, in_digest, _out_digest) in proved {
+        assert_eq!(
+            folded_proof.public_inputs[8..16]
+                .iter()
+                .map(|v| v.to_canonical_u64())
+                .collect::<Vec<_>>(),
+            in_digest.iter().map(|&w| w as u64).collect::<Vec<_>>(),
+            "tile digests don't chain: previous out_digest != next tile's in_digest"
+        );
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let folded_proof_t = builder.add_virtual_proof_with_pis(&folded_data.common);
+        let folded_verifier_t = builder.constant_verifier_data(&folded_data.verifier_only);
+        builder.verify_proof::<C>(&folded_proof_t, &folded_verifier_t, &folded_data.common);
+
+        let step_proof_t = builder.add_virtual_proof_with_pis(&step_data.common);
+        let step_verifier_t = builder.constant_verifier_data(&step_data.verifier_only);
+        builder.verify_proof::<C>(&step_proof_t, &step_verifier_t, &step_data.common);
+
+        for i in 0..8 {
+            builder.connect(folded_proof_t.public_inputs[8 + i], step_proof_t.public_inputs[i]);
+        }
+
+        for i in 0..8 {
+            builder.register_public_input(folded_proof_t.public_inputs[i]);
+        }
+        for i in 0..8 {
+            builder.register_public_input(step_proof_t.public_inputs[8 + i]);
+        }
+
+        let aggregated_data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_proof_with_pis_target(&folded_proof_t, &folded_proof);
+        pw.set_proof_with_pis_target(&step_proof_t, &step_proof);
+
+        folded_proof = aggregated_data.prove(pw)?;
+        folded_data = aggregated_data;
+    }
+
+    Ok(FoldedProof {
+        data: folded_data,
+        proof: folded_proof,
+    })
+}
@@ -0,0 +1,14 @@
+//! Shared gadgets used by the veritas benchmark binaries.
+//!
+//! Each `examples/*-benchmark.rs` proves that an image transform (crop,
+//! resize, blur, grayscale) was applied correctly, then measures circuit
+//! build / prove / verify time in a VIMz-compatible format. This crate holds
+//! the gadgets shared across those benchmarks so the binaries stay focused
+//! on the per-transform circuit logic.
+
+pub mod ecdsa;
+pub mod hashing;
+pub mod ivc;
+pub mod lookup;
+pub mod serialize;
+pub mod steps;
@@ -0,0 +1,49 @@
+//! Byte-validity lookup table.
+//!
+//! Before this module, pixel targets were wired into circuits with no
+//! constraint that they actually hold byte values: a malicious prover could
+//! supply any field element as a "pixel". This registers a `[0, 255]`
+//! identity lookup table (via plonky2's lookup-gate machinery) so pixel
+//! targets can be membership-checked against it in a single lookup, instead
+//! of the 8 `range_check` bit-decomposition gates a native range check would
+//! cost.
+
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::target::Target;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+/// Handle to the registered byte-validity table, returned by
+/// [`add_byte_range_table`] and consumed by [`constrain_byte`].
+#[derive(Clone, Copy)]
+pub struct ByteRangeTable(usize);
+
+/// Registers an identity lookup table over `[0, 255]`. Call once per
+/// circuit and reuse the returned handle for every pixel target.
+pub fn add_byte_range_table<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+) -> ByteRangeTable {
+    let pairs: Vec<(u16, u16)> = (0..=255).map(|b| (b, b)).collect();
+    ByteRangeTable(builder.add_lookup_table_from_pairs(pairs))
+}
+
+/// Constrains `target` to be a valid byte (`0..=255`) via a single lookup
+/// against the byte-range table, instead of decomposing it into 8 bits.
+pub fn constrain_byte<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    table: ByteRangeTable,
+    target: Target,
+) {
+    builder.add_lookup_from_index(target, table.0);
+}
+
+/// Constrains every target in `targets` to be a valid byte.
+pub fn constrain_bytes<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    table: ByteRangeTable,
+    targets: &[Target],
+) {
+    for &t in targets {
+        constrain_byte(builder, table, t);
+    }
+}
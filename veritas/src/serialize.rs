@@ -0,0 +1,82 @@
+//! Persisting proofs to disk so they can be verified by a third party
+//! without re-running the prover.
+//!
+//! The `examples/*-benchmark.rs` binaries prove and verify in the same
+//! process; all but `crop-benchmark.rs`, which writes its proof out through
+//! this module, then discard the proof. To hand a proof to someone else (along
+//! with the edited image) we need to serialize exactly what `examples/verify.rs`
+//! needs to check it: the proof itself, the circuit's `VerifierOnlyCircuitData`
+//! (the verifying key), and its `CommonCircuitData` (gate layout) — never the
+//! `CircuitData`'s prover-only state or generators, since a detached verifier
+//! never proves anything.
+
+use anyhow::{anyhow, Result};
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_data::{CircuitData, CommonCircuitData, VerifierOnlyCircuitData};
+use plonky2::plonk::config::GenericConfig;
+use plonky2::plonk::proof::ProofWithPublicInputs;
+use plonky2::util::serialization::DefaultGateSerializer;
+use std::fs;
+use std::path::Path;
+
+const PROOF_FILE: &str = "proof.bin";
+const VERIFIER_ONLY_FILE: &str = "verifier_only.bin";
+const COMMON_FILE: &str = "common.bin";
+
+/// Everything a detached verifier needs: the proof plus the verifying key
+/// data (`verifier_only`, `common`) for the circuit that produced it.
+pub struct ProofBundle<F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    pub proof: ProofWithPublicInputs<F, C, D>,
+    pub verifier_only: VerifierOnlyCircuitData<C, D>,
+    pub common: CommonCircuitData<F, D>,
+}
+
+/// Writes `proof` and the verifying-key half of `data` to `dir` as three
+/// files (`proof.bin`, `verifier_only.bin`, `common.bin`), creating `dir` if
+/// it doesn't exist.
+pub fn write_proof_bundle<F, C, const D: usize>(
+    dir: &Path,
+    data: &CircuitData<F, C, D>,
+    proof: &ProofWithPublicInputs<F, C, D>,
+) -> Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(PROOF_FILE), proof.to_bytes())?;
+    fs::write(
+        dir.join(VERIFIER_ONLY_FILE),
+        data.verifier_only
+            .to_bytes()
+            .map_err(|e| anyhow!("failed to serialize verifier-only data: {e:?}"))?,
+    )?;
+    fs::write(
+        dir.join(COMMON_FILE),
+        data.common
+            .to_bytes(&DefaultGateSerializer)
+            .map_err(|e| anyhow!("failed to serialize common circuit data: {e:?}"))?,
+    )?;
+    Ok(())
+}
+
+/// Reads back a [`ProofBundle`] written by [`write_proof_bundle`].
+pub fn read_proof_bundle<F, C, const D: usize>(dir: &Path) -> Result<ProofBundle<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let common = CommonCircuitData::<F, D>::from_bytes(fs::read(dir.join(COMMON_FILE))?, &DefaultGateSerializer)
+        .map_err(|e| anyhow!("failed to deserialize common circuit data: {e:?}"))?;
+    let verifier_only = VerifierOnlyCircuitData::<C, D>::from_bytes(fs::read(dir.join(VERIFIER_ONLY_FILE))?)
+        .map_err(|e| anyhow!("failed to deserialize verifier-only data: {e:?}"))?;
+    let proof = ProofWithPublicInputs::<F, C, D>::from_bytes(fs::read(dir.join(PROOF_FILE))?, &common)
+        .map_err(|e| anyhow!("failed to deserialize proof: {e:?}"))?;
+
+    Ok(ProofBundle { proof, verifier_only, common })
+}
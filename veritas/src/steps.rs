@@ -0,0 +1,549 @@
+//! Per-transform circuits exposed as [`StepCircuit`]s for IVC folding.
+//!
+//! Each function here builds the same circuit as the corresponding
+//! `examples/*-benchmark.rs` binary (crop/resize/blur/gray), except that
+//! [`build_blur_step`] builds the whole image as one circuit with separate
+//! input and output digests, where `blur-benchmark.rs` splits it into row
+//! tiles chained through one running digest. Instead of
+//! proving and timing it standalone, each returns a [`StepCircuit`] so
+//! [`crate::ivc::fold_steps`] can chain it onto the rest of an edit
+//! pipeline. The public inputs are always `(in_digest, out_digest)`, 8 words
+//! each, matching [`crate::hashing::commit_image`].
+
+use crate::hashing;
+use crate::lookup;
+use anyhow::Result;
+use plonky2::field::extension::Extendable;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::{CircuitConfig, CircuitData};
+use plonky2::plonk::config::GenericConfig;
+use serde_json::Value;
+use std::fs;
+
+/// A built circuit ready to prove, plus the plaintext digests its public
+/// inputs will evaluate to. Pipelines use the digests to assert that one
+/// step's output feeds the next step's input before even generating proofs.
+pub struct StepCircuit<F, C, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    pub data: CircuitData<F, C, D>,
+    pub witness: PartialWitness<F>,
+    pub in_digest: [u32; 8],
+    pub out_digest: [u32; 8],
+}
+
+fn load_json(json_path: &str) -> Result<Value> {
+    let json_str = fs::read_to_string(json_path)?;
+    Ok(serde_json::from_str(&json_str)?)
+}
+
+/// Builds the grayscale step: commits to the original RGB image and the
+/// true grayscale byte `(299R + 587G + 114B) / 1000` (see `gray-benchmark.rs`).
+pub fn build_gray_step<F, C, const D: usize>(json_path: &str) -> Result<StepCircuit<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let data = load_json(json_path)?;
+    let original = data["original"].as_array().unwrap();
+
+    let mut r_vals = Vec::new();
+    let mut g_vals = Vec::new();
+    let mut b_vals = Vec::new();
+    for row in original {
+        for pixel in row.as_array().unwrap() {
+            let rgb = pixel.as_array().unwrap();
+            r_vals.push(rgb[0].as_u64().unwrap() as u32);
+            g_vals.push(rgb[1].as_u64().unwrap() as u32);
+            b_vals.push(rgb[2].as_u64().unwrap() as u32);
+        }
+    }
+    let total_pixels = r_vals.len();
+
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+    let mut pw = PartialWitness::new();
+
+    let byte_table = lookup::add_byte_range_table(&mut builder);
+
+    let mut r_targets = Vec::new();
+    let mut g_targets = Vec::new();
+    let mut b_targets = Vec::new();
+    let mut original_bytes = Vec::new();
+    let mut q_targets = Vec::new();
+    let mut rem_targets = Vec::new();
+
+    for i in 0..total_pixels {
+        let r = builder.add_virtual_target();
+        let g = builder.add_virtual_target();
+        let b = builder.add_virtual_target();
+        lookup::constrain_bytes(&mut builder, byte_table, &[r, g, b]);
+        original_bytes.push(r);
+        original_bytes.push(g);
+        original_bytes.push(b);
+
+        let mut all = Vec::new();
+        all.push(builder.mul_const(F::from_canonical_u32(299), r));
+        all.push(builder.mul_const(F::from_canonical_u32(587), g));
+        all.push(builder.mul_const(F::from_canonical_u32(114), b));
+        let s = builder.add_many(all);
+
+        let sum = r_vals[i] * 299 + g_vals[i] * 587 + b_vals[i] * 114;
+        let q_val = sum / 1000;
+        let rem_val = sum - q_val * 1000;
+
+        let q = builder.add_virtual_target();
+        let rem = builder.add_virtual_target();
+        let thousand_q = builder.mul_const(F::from_canonical_u32(1000), q);
+        let reconstructed = builder.add(thousand_q, rem);
+        builder.connect(s, reconstructed);
+        // See gray-benchmark.rs: range_check(rem, 10) alone admits
+        // rem in [1000, 1023], letting a prover shave 1 off `q` and add
+        // 1000 to `rem`. Also range-check rem + 24 to cap rem at 999.
+        builder.range_check(rem, 10);
+        let rem_plus_24 = builder.add_const(rem, F::from_canonical_u32(24));
+        builder.range_check(rem_plus_24, 10);
+        lookup::constrain_byte(&mut builder, byte_table, q);
+
+        q_targets.push(q);
+        rem_targets.push(rem);
+
+        r_targets.push(r);
+        g_targets.push(g);
+        b_targets.push(b);
+
+        pw.set_target(q, F::from_canonical_u32(q_val));
+        pw.set_target(rem, F::from_canonical_u32(rem_val));
+    }
+
+    let original_bytes_plain: Vec<u32> = (0..total_pixels)
+        .flat_map(|i| [r_vals[i], g_vals[i], b_vals[i]])
+        .collect();
+    let out_vals: Vec<u32> = (0..total_pixels)
+        .map(|i| (r_vals[i] * 299 + g_vals[i] * 587 + b_vals[i] * 114) / 1000)
+        .collect();
+    let in_digest = hashing::commit_image_plain(&original_bytes_plain);
+    let out_digest = hashing::commit_image_plain(&out_vals);
+
+    let original_digest = hashing::commit_image(&mut builder, &original_bytes);
+    let output_digest = hashing::commit_image(&mut builder, &q_targets);
+    for word in original_digest.into_iter().chain(output_digest) {
+        builder.register_public_input(word);
+    }
+
+    for i in 0..total_pixels {
+        pw.set_target(r_targets[i], F::from_canonical_u32(r_vals[i]));
+        pw.set_target(g_targets[i], F::from_canonical_u32(g_vals[i]));
+        pw.set_target(b_targets[i], F::from_canonical_u32(b_vals[i]));
+    }
+
+    Ok(StepCircuit {
+        data: builder.build::<C>(),
+        witness: pw,
+        in_digest,
+        out_digest,
+    })
+}
+
+/// Builds the crop step: commits to the full original image and the
+/// cropped region, which is wired directly to the matching original targets
+/// (see `crop-benchmark.rs`).
+pub fn build_crop_step<F, C, const D: usize>(json_path: &str) -> Result<StepCircuit<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let data = load_json(json_path)?;
+    let original = data["original"].as_array().unwrap();
+    let cropped = data["cropped"].as_array().unwrap();
+    let crop_x = data["crop_x"].as_u64().unwrap() as usize;
+    let crop_y = data["crop_y"].as_u64().unwrap() as usize;
+
+    let mut w_r_vals = Vec::new();
+    for row in original {
+        w_r_vals.push(
+            row.as_array()
+                .unwrap()
+                .iter()
+                .map(|p| p.as_u64().unwrap() as u32)
+                .collect::<Vec<_>>(),
+        );
+    }
+    let mut x_r_vals = Vec::new();
+    for row in cropped {
+        x_r_vals.push(
+            row.as_array()
+                .unwrap()
+                .iter()
+                .map(|p| p.as_u64().unwrap() as u32)
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    let orig_width = w_r_vals[0].len();
+    let crop_width = x_r_vals[0].len();
+    let crop_height = x_r_vals.len();
+    let old_size = w_r_vals.len() * orig_width;
+    let w_r_vals_flat: Vec<u32> = w_r_vals.iter().flatten().copied().collect();
+    let x_r_vals_flat: Vec<u32> = x_r_vals.iter().flatten().copied().collect();
+
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+    let mut pw = PartialWitness::new();
+
+    let byte_table = lookup::add_byte_range_table(&mut builder);
+    let mut orig_targets = Vec::new();
+    for _ in 0..old_size {
+        let t = builder.add_virtual_target();
+        lookup::constrain_byte(&mut builder, byte_table, t);
+        orig_targets.push(t);
+    }
+    let mut w_r_targets = Vec::new();
+    for i in 0..crop_height {
+        for j in 0..crop_width {
+            let orig_row = crop_y + i;
+            let orig_col = crop_x + j;
+            w_r_targets.push(orig_targets[orig_row * orig_width + orig_col]);
+        }
+    }
+
+    let in_digest = hashing::commit_image_plain(&w_r_vals_flat);
+    let out_digest = hashing::commit_image_plain(&x_r_vals_flat);
+
+    let original_digest = hashing::commit_image(&mut builder, &orig_targets);
+    let output_digest = hashing::commit_image(&mut builder, &w_r_targets);
+    for word in original_digest.into_iter().chain(output_digest) {
+        builder.register_public_input(word);
+    }
+
+    for i in 0..old_size {
+        pw.set_target(orig_targets[i], F::from_canonical_u32(w_r_vals_flat[i]));
+    }
+
+    Ok(StepCircuit {
+        data: builder.build::<C>(),
+        witness: pw,
+        in_digest,
+        out_digest,
+    })
+}
+
+fn get_positions(
+    i: usize,
+    j: usize,
+    w_orig: usize,
+    h_orig: usize,
+    w_new: usize,
+    h_new: usize,
+) -> (usize, usize, usize, usize) {
+    let x_l = if w_new > 1 { (w_orig - 1) * j / (w_new - 1) } else { 0 };
+    let y_l = if h_new > 1 { (h_orig - 1) * i / (h_new - 1) } else { 0 };
+
+    let x_h = if w_new > 1 && x_l * (w_new - 1) == (w_orig - 1) * j { x_l } else { (x_l + 1).min(w_orig - 1) };
+    let y_h = if h_new > 1 && y_l * (h_new - 1) == (h_orig - 1) * i { y_l } else { (y_l + 1).min(h_orig - 1) };
+
+    (x_l, y_l, x_h, y_h)
+}
+
+fn get_ratios(i: usize, j: usize, w_orig: usize, h_orig: usize, w_new: usize, h_new: usize) -> (usize, usize) {
+    let x_ratio_weighted = if w_new > 1 {
+        ((w_orig - 1) * j) - (w_new - 1) * ((w_orig - 1) * j / (w_new - 1))
+    } else {
+        0
+    };
+    let y_ratio_weighted = if h_new > 1 {
+        ((h_orig - 1) * i) - (h_new - 1) * ((h_orig - 1) * i / (h_new - 1))
+    } else {
+        0
+    };
+    (x_ratio_weighted, y_ratio_weighted)
+}
+
+/// Builds the resize step: commits to the full original image and the
+/// resized output bytes, each constrained against its bilinear-interpolation
+/// weighted sum (see `resize-benchmark.rs`).
+pub fn build_resize_step<F, C, const D: usize>(json_path: &str) -> Result<StepCircuit<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let data = load_json(json_path)?;
+    let original = data["original"].as_array().unwrap();
+    let resized = data["resized"].as_array().unwrap();
+
+    let mut w_r_vals = Vec::new();
+    for row in original {
+        w_r_vals.push(
+            row.as_array()
+                .unwrap()
+                .iter()
+                .map(|p| p.as_u64().unwrap() as u32)
+                .collect::<Vec<_>>(),
+        );
+    }
+    let mut x_r_vals = Vec::new();
+    for row in resized {
+        x_r_vals.push(
+            row.as_array()
+                .unwrap()
+                .iter()
+                .map(|p| p.as_u64().unwrap() as u32)
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    let h_orig = w_r_vals.len();
+    let w_orig = w_r_vals[0].len();
+    let h_new = x_r_vals.len();
+    let w_new = x_r_vals[0].len();
+    let denom = if w_new > 1 && h_new > 1 { (w_new - 1) * (h_new - 1) } else { 1 };
+
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+    let mut pw = PartialWitness::new();
+
+    let byte_table = lookup::add_byte_range_table(&mut builder);
+    let mut orig_targets = Vec::new();
+    for _ in 0..h_orig {
+        let mut row = Vec::new();
+        for _ in 0..w_orig {
+            let t = builder.add_virtual_target();
+            lookup::constrain_byte(&mut builder, byte_table, t);
+            row.push(t);
+        }
+        orig_targets.push(row);
+    }
+
+    // The weighted sum `s` isn't a byte (bounded by `255*denom`, which
+    // exceeds 255 for any non-trivial resize), so — as in gray/blur — we
+    // constrain the true output byte `new` and a remainder: `s = new*denom +
+    // rem`, `new` a valid byte via the lookup table, `rem` the round-to-
+    // nearest error bounded to exactly `[-shift, denom - shift)`, a window
+    // of width `denom` (not `2*denom`, which would admit two adjacent `new`
+    // values for the same `s`). See resize-benchmark.rs for the derivation;
+    // `rem_bits`/`rem_slack` are the same for every pixel since `denom`
+    // doesn't vary within one resize.
+    let shift = denom / 2;
+    let rem_bits = (usize::BITS - (denom.max(2) - 1).leading_zeros()) as usize;
+    let rem_slack = (1usize << rem_bits) - denom;
+    let shift_target = builder.constant(F::from_canonical_u64(shift as u64));
+
+    let mut new_targets = Vec::new();
+    let mut rem_shifted_targets = Vec::new();
+    let mut rem_shifted_vals = Vec::new();
+    for i in 0..h_new {
+        for j in 0..w_new {
+            let (x_l, y_l, x_h, y_h) = get_positions(i, j, w_orig, h_orig, w_new, h_new);
+            let (x_ratio_weighted, y_ratio_weighted) = get_ratios(i, j, w_orig, h_orig, w_new, h_new);
+
+            let a = orig_targets[y_l][x_l];
+            let b = orig_targets[y_l][x_h];
+            let c = orig_targets[y_h][x_l];
+            let d = orig_targets[y_h][x_h];
+
+            let a_const = ((w_new - 1 - x_ratio_weighted) * (h_new - 1 - y_ratio_weighted)) as u32;
+            let b_const = (x_ratio_weighted * (h_new - 1 - y_ratio_weighted)) as u32;
+            let c_const = (y_ratio_weighted * (w_new - 1 - x_ratio_weighted)) as u32;
+            let d_const = (x_ratio_weighted * y_ratio_weighted) as u32;
+
+            let mut all = Vec::new();
+            all.push(builder.mul_const(F::from_canonical_u32(a_const), a));
+            all.push(builder.mul_const(F::from_canonical_u32(b_const), b));
+            all.push(builder.mul_const(F::from_canonical_u32(c_const), c));
+            all.push(builder.mul_const(F::from_canonical_u32(d_const), d));
+            let s = builder.add_many(all);
+
+            let new = builder.add_virtual_target();
+            lookup::constrain_byte(&mut builder, byte_table, new);
+
+            let rem_shifted = builder.add_virtual_target();
+            let rem = builder.sub(rem_shifted, shift_target);
+            let new_times_denom = builder.mul_const(F::from_canonical_u32(denom as u32), new);
+            let reconstructed = builder.add(new_times_denom, rem);
+            builder.connect(s, reconstructed);
+
+            builder.range_check(rem_shifted, rem_bits);
+            let rem_shifted_top = builder.add_const(rem_shifted, F::from_canonical_u32(rem_slack as u32));
+            builder.range_check(rem_shifted_top, rem_bits);
+
+            let a_v = w_r_vals[y_l][x_l] as u64;
+            let b_v = w_r_vals[y_l][x_h] as u64;
+            let c_v = w_r_vals[y_h][x_l] as u64;
+            let d_v = w_r_vals[y_h][x_h] as u64;
+            let s_v = a_v * a_const as u64 + b_v * b_const as u64 + c_v * c_const as u64 + d_v * d_const as u64;
+            let rem_v = s_v as i64 - (x_r_vals[i][j] as i64) * (denom as i64);
+
+            new_targets.push(new);
+            rem_shifted_targets.push(rem_shifted);
+            rem_shifted_vals.push((rem_v + shift as i64) as u32);
+        }
+    }
+
+    let orig_vals_flat: Vec<u32> = w_r_vals.iter().flatten().copied().collect();
+    let output_vals_flat: Vec<u32> = x_r_vals.iter().flatten().copied().collect();
+    let in_digest = hashing::commit_image_plain(&orig_vals_flat);
+    let out_digest = hashing::commit_image_plain(&output_vals_flat);
+
+    let orig_targets_flat: Vec<_> = orig_targets.iter().flatten().copied().collect();
+    let original_digest = hashing::commit_image(&mut builder, &orig_targets_flat);
+    let output_digest = hashing::commit_image(&mut builder, &new_targets);
+    for word in original_digest.into_iter().chain(output_digest) {
+        builder.register_public_input(word);
+    }
+
+    for i in 0..h_orig {
+        for j in 0..w_orig {
+            pw.set_target(orig_targets[i][j], F::from_canonical_u32(w_r_vals[i][j]));
+        }
+    }
+    for i in 0..h_new {
+        for j in 0..w_new {
+            let idx = i * w_new + j;
+            pw.set_target(new_targets[idx], F::from_canonical_u32(x_r_vals[i][j]));
+            pw.set_target(rem_shifted_targets[idx], F::from_canonical_u32(rem_shifted_vals[idx]));
+        }
+    }
+
+    Ok(StepCircuit {
+        data: builder.build::<C>(),
+        witness: pw,
+        in_digest,
+        out_digest,
+    })
+}
+
+/// Builds the blur step: commits to the full original image and the output
+/// image (border pixels unchanged, the small interior blur region holds the
+/// box-blurred result), see `blur-benchmark.rs`.
+pub fn build_blur_step<F, C, const D: usize>(json_path: &str) -> Result<StepCircuit<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    const BLUR_H: usize = 6;
+    const BLUR_W: usize = 6;
+
+    let data = load_json(json_path)?;
+    let original = data["original"].as_array().unwrap();
+    let blurred = data["blurred"].as_array().unwrap();
+
+    let mut w_r_vals = Vec::new();
+    for row in original {
+        w_r_vals.push(
+            row.as_array()
+                .unwrap()
+                .iter()
+                .map(|p| p.as_u64().unwrap() as u32)
+                .collect::<Vec<_>>(),
+        );
+    }
+    let mut x_r_vals = Vec::new();
+    for row in blurred {
+        x_r_vals.push(
+            row.as_array()
+                .unwrap()
+                .iter()
+                .map(|p| p.as_u64().unwrap() as u32)
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    let h = w_r_vals.len();
+    let w = w_r_vals[0].len();
+
+    let config = CircuitConfig::standard_recursion_config();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+    let mut pw = PartialWitness::new();
+
+    let byte_table = lookup::add_byte_range_table(&mut builder);
+    let mut w_r_targets = Vec::new();
+    for _ in 0..h {
+        let mut row = Vec::new();
+        for _ in 0..w {
+            let t = builder.add_virtual_target();
+            lookup::constrain_byte(&mut builder, byte_table, t);
+            row.push(t);
+        }
+        w_r_targets.push(row);
+    }
+
+    let mut x_r_targets = Vec::new();
+    let mut out_targets = Vec::new();
+    for i in 0..h {
+        let mut x_r_row = Vec::new();
+        let mut out_row = Vec::with_capacity(w);
+        for j in 0..w {
+            if i > 0 && i < 1 + BLUR_H && j > 0 && j < 1 + BLUR_W {
+                let mut all_r = Vec::new();
+                for (di, dj) in [(-1i64, -1i64), (-1, 0), (-1, 1), (0, -1), (0, 0), (0, 1), (1, -1), (1, 0), (1, 1)] {
+                    let ni = (i as i64 + di) as usize;
+                    let nj = (j as i64 + dj) as usize;
+                    all_r.push(w_r_targets[ni][nj]);
+                }
+                let s_r = builder.add_many(all_r);
+                let s_r_shift = builder.add_const(s_r, F::from_canonical_u32(4));
+
+                let x_r = builder.add_virtual_target();
+                x_r_row.push(x_r);
+                let x_r_times_9 = builder.mul_const(F::from_canonical_u32(9), x_r);
+                let rem_r = builder.sub(s_r_shift, x_r_times_9);
+                builder.range_check(rem_r, 4);
+                let rem_r_plus_7 = builder.add_const(rem_r, F::from_canonical_u32(7));
+                builder.range_check(rem_r_plus_7, 4);
+
+                out_row.push(x_r);
+            } else {
+                out_row.push(w_r_targets[i][j]);
+            }
+        }
+        if !x_r_row.is_empty() {
+            x_r_targets.push(x_r_row);
+        }
+        out_targets.push(out_row);
+    }
+
+    let w_r_vals_flat: Vec<u32> = w_r_vals.iter().flatten().copied().collect();
+    let mut out_vals_flat = Vec::with_capacity(h * w);
+    for i in 0..h {
+        for j in 0..w {
+            if i > 0 && i < 1 + BLUR_H && j > 0 && j < 1 + BLUR_W {
+                out_vals_flat.push(x_r_vals[i][j]);
+            } else {
+                out_vals_flat.push(w_r_vals[i][j]);
+            }
+        }
+    }
+    let in_digest = hashing::commit_image_plain(&w_r_vals_flat);
+    let out_digest = hashing::commit_image_plain(&out_vals_flat);
+
+    let w_r_targets_flat: Vec<_> = w_r_targets.iter().flatten().copied().collect();
+    let out_targets_flat: Vec<_> = out_targets.iter().flatten().copied().collect();
+    let original_digest = hashing::commit_image(&mut builder, &w_r_targets_flat);
+    let output_digest = hashing::commit_image(&mut builder, &out_targets_flat);
+    for word in original_digest.into_iter().chain(output_digest) {
+        builder.register_public_input(word);
+    }
+
+    for i in 0..h {
+        for j in 0..w {
+            pw.set_target(w_r_targets[i][j], F::from_canonical_u32(w_r_vals[i][j]));
+        }
+    }
+    for i in 0..BLUR_H {
+        for j in 0..BLUR_W {
+            pw.set_target(x_r_targets[i][j], F::from_canonical_u32(x_r_vals[i + 1][j + 1]));
+        }
+    }
+
+    Ok(StepCircuit {
+        data: builder.build::<C>(),
+        witness: pw,
+        in_digest,
+        out_digest,
+    })
+}